@@ -1,6 +1,25 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::time::Duration;
+
 use avian2d::prelude::*;
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+use bevy_ggrs::{
+    AddRollbackCommandExtension, GgrsApp, GgrsPlugin, GgrsSchedule, LocalInputs, LocalPlayers,
+    PlayerInputs, ReadInputs,
+};
+use bytemuck::{Pod, Zeroable};
+use clap::Parser;
+use ggrs::{PlayerType, SessionBuilder, UdpNonBlockingSocket};
 use leafwing_input_manager::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
 
 #[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
 enum Action {
@@ -14,46 +33,292 @@ struct GameArea {
     height: f32,
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 struct AccelerationTimer(Timer);
 
+#[derive(Resource, Default, Clone, Copy)]
+struct Score {
+    left: u32,
+    right: u32,
+}
+
+// Seeded identically on both GGRS peers so ball serves stay in sync across rollbacks;
+// rollback-tracked itself so resimulation replays the same sequence of draws.
+#[derive(Resource, Clone)]
+struct ServeRng(StdRng);
+
+impl Default for ServeRng {
+    fn default() -> Self {
+        ServeRng(StdRng::seed_from_u64(0))
+    }
+}
+
+#[derive(Resource)]
+struct WinningScore(u32);
+
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+enum GameState {
+    #[default]
+    Playing,
+    GameOver,
+}
+
+const INPUT_UP: u8 = 1 << 0;
+const INPUT_DOWN: u8 = 1 << 1;
+
+// Packed as Pod/Zeroable so GGRS can serialize it for rollback
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Pod, Zeroable)]
+struct PlayerInput {
+    buttons: u8,
+}
+
+struct GgrsConfig;
+
+impl ggrs::Config for GgrsConfig {
+    type Input = PlayerInput;
+    type State = u8;
+    type Address = SocketAddr;
+}
+
+// Controls the right-hand paddle: a second local player, or the AI.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+enum GameMode {
+    SinglePlayer,
+    LocalVersus,
+}
+
+fn resolve_game_mode(local_versus: bool) -> GameMode {
+    if local_versus {
+        GameMode::LocalVersus
+    } else {
+        GameMode::SinglePlayer
+    }
+}
+
+const GAME_CONFIG_PATH: &str = "assets/config/game.ron";
+
+// Up/Down key pair, deserialized straight into KeyCode (e.g. "ArrowUp", "KeyW", "Space")
+#[derive(Deserialize, Clone)]
+struct KeyBindings {
+    up: KeyCode,
+    down: KeyCode,
+}
+
+impl KeyBindings {
+    fn to_input_map(&self) -> InputMap<Action> {
+        InputMap::new([(Action::Up, self.up), (Action::Down, self.down)])
+    }
+}
+
+// Loaded once at startup from GAME_CONFIG_PATH, falling back to Default below
+#[derive(Resource, Deserialize, Clone)]
+#[serde(default)]
+struct GameConfig {
+    ball_initial_velocity: (f32, f32),
+    acceleration_factor: f32,
+    acceleration_interval_secs: f32,
+    paddle_speed: f32,
+    paddle_width: f32,
+    paddle_height: f32,
+    border_thickness: f32,
+    player_one_keys: KeyBindings,
+    player_two_keys: KeyBindings,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            ball_initial_velocity: (200.0, 200.0),
+            acceleration_factor: 1.001,
+            acceleration_interval_secs: 0.1,
+            paddle_speed: 300.0,
+            paddle_width: 10.0,
+            paddle_height: 50.0,
+            border_thickness: 20.0,
+            player_one_keys: KeyBindings {
+                up: KeyCode::ArrowUp,
+                down: KeyCode::ArrowDown,
+            },
+            player_two_keys: KeyBindings {
+                up: KeyCode::KeyW,
+                down: KeyCode::KeyS,
+            },
+        }
+    }
+}
+
+fn load_game_config() -> GameConfig {
+    let Ok(file) = File::open(GAME_CONFIG_PATH) else {
+        return GameConfig::default();
+    };
+
+    ron::de::from_reader(BufReader::new(file)).unwrap_or_else(|err| {
+        warn!("failed to parse {GAME_CONFIG_PATH}, using defaults: {err}");
+        GameConfig::default()
+    })
+}
+
+/// CLI options for the session. Left unset, the game runs fully offline.
+#[derive(Parser, Debug, Clone)]
+struct NetArgs {
+    /// Local UDP port to bind for the P2P session. Passing this opts into online play.
+    #[arg(long)]
+    local_port: Option<u16>,
+    /// Socket address of the remote peer (required unless --synctest is set)
+    #[arg(long)]
+    remote_addr: Option<SocketAddr>,
+    /// Run a local synctest session to check determinism instead of connecting to a peer
+    #[arg(long)]
+    synctest: bool,
+    /// Control the right-hand paddle with a second local player instead of the AI
+    #[arg(long)]
+    local_versus: bool,
+}
+
+// GGRS player handle this paddle is controlled by
+#[derive(Component)]
+struct Player(usize);
+
 fn main() {
-    App::new()
-        .add_plugins((
-            DefaultPlugins,
-            PhysicsPlugins::default(),
-            InputManagerPlugin::<Action>::default(),
-        ))
-        .insert_resource(ClearColor(Color::srgb(1.0, 1.0, 1.0)))
-        .insert_resource(AccelerationTimer(Timer::from_seconds(
-            0.1,
-            TimerMode::Repeating,
-        )))
+    let net_args = NetArgs::parse();
+    let game_config = load_game_config();
+
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins,
+        PhysicsPlugins::default(),
+        InputManagerPlugin::<Action>::default(),
+    ))
+    .insert_resource(ClearColor(Color::srgb(1.0, 1.0, 1.0)))
+    .insert_resource(AccelerationTimer(Timer::from_seconds(
+        game_config.acceleration_interval_secs,
+        TimerMode::Repeating,
+    )))
+    .insert_resource(Score::default())
+    .insert_resource(ServeRng::default())
+    .insert_resource(WinningScore(11))
+    .insert_resource(resolve_game_mode(net_args.local_versus))
+    .insert_resource(game_config)
+    .init_state::<GameState>()
+    .add_systems(
+        Startup,
+        (
+            setup_game_area,
+            setup_camera,
+            (
+                spawn_local_paddle,
+                spawn_opponent_paddle,
+                spawn_border,
+                spawn_ball,
+                spawn_scoreboard,
+            )
+                .after(setup_game_area)
+                .after(setup_camera),
+        ),
+    )
+    .add_systems(Update, (update_scoreboard, resize_game_area))
+    .add_systems(OnEnter(GameState::GameOver), spawn_game_over_text)
+    .register_type::<Border>()
+    .register_type::<BorderSide>()
+    .register_type::<Ball>()
+    .register_type::<Paddle>();
+
+    match net_args.local_port {
+        Some(local_port) => setup_online_session(&mut app, local_port, &net_args),
+        None => {
+            app.add_systems(
+                Update,
+                (
+                    move_paddle,
+                    ai_move_paddle,
+                    check_collisions,
+                    accelerate_ball,
+                    check_goals,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+        }
+    }
+
+    app.run();
+}
+
+const GGRS_FPS: u32 = 60;
+
+fn setup_online_session(app: &mut App, local_port: u16, net_args: &NetArgs) {
+    app.add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_systems(ReadInputs, read_local_inputs)
+        .rollback_component_with_copy::<Transform>()
+        .rollback_component_with_copy::<LinearVelocity>()
+        .rollback_resource_with_copy::<Score>()
+        .rollback_resource_with_clone::<AccelerationTimer>()
+        .rollback_resource_with_clone::<ServeRng>()
+        .rollback_resource_with_clone::<State<GameState>>()
+        .set_rollback_schedule_fps(GGRS_FPS)
         .add_systems(
-            Startup,
+            GgrsSchedule,
             (
-                setup_game_area,
-                setup_camera,
-                (spawn_local_paddle, spawn_border, spawn_ball)
-                    .after(setup_game_area)
-                    .after(setup_camera),
-            ),
-        )
-        .add_systems(Update, (move_paddle, check_collisions, accelerate_ball))
-        .register_type::<Border>()
-        .register_type::<BorderSide>()
-        .register_type::<Ball>()
-        .register_type::<Paddle>()
-        .run();
+                move_paddle_p2p,
+                step_physics,
+                check_collisions,
+                accelerate_ball,
+                check_goals,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
+
+    // avian2d normally steps itself every frame; under rollback it must only advance once per
+    // simulated GGRS frame, driven by `step_physics` advancing this clock by a fixed amount.
+    app.world_mut().resource_mut::<Time<Physics>>().pause();
+
+    if net_args.synctest {
+        let session = SessionBuilder::<GgrsConfig>::new()
+            .with_num_players(2)
+            .add_player(PlayerType::Local, 0)
+            .expect("failed to add local player")
+            .add_player(PlayerType::Local, 1)
+            .expect("failed to add local player")
+            .start_synctest_session()
+            .expect("failed to start synctest session");
+
+        app.insert_resource(bevy_ggrs::Session::SyncTest(session));
+    } else {
+        let remote_addr = net_args
+            .remote_addr
+            .expect("--remote-addr is required outside of --synctest");
+        let socket = UdpNonBlockingSocket::bind_to_port(local_port)
+            .expect("failed to bind local UDP socket");
+
+        let session = SessionBuilder::<GgrsConfig>::new()
+            .with_num_players(2)
+            .add_player(PlayerType::Local, 0)
+            .expect("failed to add local player")
+            .add_player(PlayerType::Remote(remote_addr), 1)
+            .expect("failed to add remote player")
+            .start_p2p_session(socket)
+            .expect("failed to start P2P session");
+
+        app.insert_resource(bevy_ggrs::Session::P2P(session));
+    }
 }
 
+// Manually advances one physics step per simulated GGRS frame instead of avian2d's own schedule
+fn step_physics(world: &mut World) {
+    let dt = Duration::from_secs_f64(1.0 / GGRS_FPS as f64);
+    world.resource_mut::<Time<Physics>>().advance_by(dt);
+    world.run_schedule(PhysicsSchedule);
+}
+
+const GAME_AREA_PADDING: f32 = 20.0;
+
 fn setup_game_area(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
     let window = window_query.single();
 
     // Add some padding to keep elements away from the window edges
-    let padding = 20.0;
-    let game_width = window.width() - padding * 2.0;
-    let game_height = window.height() - padding * 2.0;
+    let game_width = window.width() - GAME_AREA_PADDING * 2.0;
+    let game_height = window.height() - GAME_AREA_PADDING * 2.0;
 
     commands.insert_resource(GameArea {
         width: game_width,
@@ -83,8 +348,6 @@ struct Border;
 #[derive(Component, Default, Reflect)]
 enum BorderSide {
     #[default]
-    Left,
-    Right,
     Top,
     Bottom,
 }
@@ -117,21 +380,57 @@ struct Ball;
         Transform,
         Sprite,
 )]
-struct Paddle;
+struct Paddle {
+    height: f32,
+}
 
-fn spawn_local_paddle(mut commands: Commands, game_area: Res<GameArea>) {
-    let input_map = InputMap::new([
-        (Action::Up, KeyCode::ArrowUp),
-        (Action::Down, KeyCode::ArrowDown),
-    ]);
+#[derive(Component, Clone, Copy)]
+enum PaddleController {
+    Human,
+    Ai { difficulty: f32 },
+}
 
-    let paddle_width = 10.0;
-    let paddle_height = 50.0;
+fn spawn_local_paddle(mut commands: Commands, game_area: Res<GameArea>, config: Res<GameConfig>) {
+    let input_map = config.player_one_keys.to_input_map();
+
+    let paddle_width = config.paddle_width;
+    let paddle_height = config.paddle_height;
     let paddle_x = -(game_area.width / 2.0) + paddle_width * 2.0; // Position paddle near left border
 
-    commands.spawn((
-        Paddle,
-        InputManagerBundle::with_map(input_map),
+    commands
+        .spawn((
+            Paddle {
+                height: paddle_height,
+            },
+            Player(0),
+            PaddleController::Human,
+            InputManagerBundle::with_map(input_map),
+            Transform::from_xyz(paddle_x, 0., 0.),
+            Collider::rectangle(paddle_width, paddle_height),
+            Sprite {
+                color: Color::srgb(0.25, 0.25, 0.25),
+                custom_size: Some(Vec2::new(paddle_width, paddle_height)),
+                ..Default::default()
+            },
+        ))
+        .add_rollback();
+}
+
+fn spawn_opponent_paddle(
+    mut commands: Commands,
+    game_area: Res<GameArea>,
+    game_mode: Res<GameMode>,
+    config: Res<GameConfig>,
+) {
+    let paddle_width = config.paddle_width;
+    let paddle_height = config.paddle_height;
+    let paddle_x = (game_area.width / 2.0) - paddle_width * 2.0; // Position paddle near right edge
+
+    let mut entity = commands.spawn((
+        Paddle {
+            height: paddle_height,
+        },
+        Player(1),
         Transform::from_xyz(paddle_x, 0., 0.),
         Collider::rectangle(paddle_width, paddle_height),
         Sprite {
@@ -140,53 +439,48 @@ fn spawn_local_paddle(mut commands: Commands, game_area: Res<GameArea>) {
             ..Default::default()
         },
     ));
+    entity.add_rollback();
+
+    match *game_mode {
+        GameMode::LocalVersus => {
+            let input_map = config.player_two_keys.to_input_map();
+            entity.insert((
+                PaddleController::Human,
+                InputManagerBundle::with_map(input_map),
+            ));
+        }
+        GameMode::SinglePlayer => {
+            entity.insert(PaddleController::Ai { difficulty: 200.0 });
+        }
+    }
 }
 
 fn spawn_ball(
     mut commands: Commands,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<GameConfig>,
 ) {
     let mesh = meshes.add(Circle::new(10.));
     let material = materials.add(Color::srgb(0.25, 0.25, 0.25));
+    let (vx, vy) = config.ball_initial_velocity;
 
-    commands.spawn((Ball, Mesh2d(mesh), MeshMaterial2d(material)));
+    commands
+        .spawn((
+            Ball,
+            LinearVelocity(Vec2::new(vx, vy)),
+            Mesh2d(mesh),
+            MeshMaterial2d(material),
+        ))
+        .add_rollback();
 }
 
-fn spawn_border(mut commands: Commands, game_area: Res<GameArea>) {
-    let border_thickness = 20.0;
-    let half_width = game_area.width / 2.0;
+fn spawn_border(mut commands: Commands, game_area: Res<GameArea>, config: Res<GameConfig>) {
     let half_height = game_area.height / 2.0;
+    let border_thickness = config.border_thickness;
 
-    // Adjust the height/width of vertical/horizontal borders to account for corners
-    let vertical_height = game_area.height + border_thickness;
-    let horizontal_width = game_area.width - border_thickness;
-
-    // Left
-    commands.spawn((
-        Border,
-        BorderSide::Left,
-        Transform::from_xyz(-half_width, 0., 0.),
-        Collider::rectangle(border_thickness, vertical_height),
-        Sprite {
-            color: Color::srgb(0.25, 0.25, 0.25),
-            custom_size: Some(Vec2::new(border_thickness, vertical_height)),
-            ..Default::default()
-        },
-    ));
-
-    // Right
-    commands.spawn((
-        Border,
-        BorderSide::Right,
-        Transform::from_xyz(half_width, 0., 0.),
-        Collider::rectangle(border_thickness, vertical_height),
-        Sprite {
-            color: Color::srgb(0.25, 0.25, 0.25),
-            custom_size: Some(Vec2::new(border_thickness, vertical_height)),
-            ..Default::default()
-        },
-    ));
+    // The left/right edges are goal zones, not walls, so only top/bottom bounce the ball
+    let horizontal_width = game_area.width;
 
     // Top
     commands.spawn((
@@ -215,10 +509,47 @@ fn spawn_border(mut commands: Commands, game_area: Res<GameArea>) {
     ));
 }
 
+fn resize_game_area(
+    mut resize_events: EventReader<WindowResized>,
+    mut game_area: ResMut<GameArea>,
+    config: Res<GameConfig>,
+    mut border_query: Query<
+        (&BorderSide, &mut Transform, &mut Collider, &mut Sprite),
+        With<Border>,
+    >,
+    mut paddle_query: Query<&mut Transform, (With<Paddle>, Without<Border>)>,
+) {
+    let Some(resize_event) = resize_events.read().last() else {
+        return;
+    };
+
+    game_area.width = resize_event.width - GAME_AREA_PADDING * 2.0;
+    game_area.height = resize_event.height - GAME_AREA_PADDING * 2.0;
+
+    let half_height = game_area.height / 2.0;
+    let horizontal_width = game_area.width;
+    let border_thickness = config.border_thickness;
+
+    for (side, mut transform, mut collider, mut sprite) in &mut border_query {
+        transform.translation.y = match side {
+            BorderSide::Top => half_height,
+            BorderSide::Bottom => -half_height,
+        };
+        *collider = Collider::rectangle(horizontal_width, border_thickness);
+        sprite.custom_size = Some(Vec2::new(horizontal_width, border_thickness));
+    }
+
+    let max_y = game_area.height / 2.0 - 30.0; // Leave some space from borders
+    for mut transform in &mut paddle_query {
+        transform.translation.y = transform.translation.y.clamp(-max_y, max_y);
+    }
+}
+
 fn move_paddle(
     mut query: Query<(&ActionState<Action>, &mut Transform), With<Paddle>>,
     time: Res<Time>,
     game_area: Res<GameArea>,
+    config: Res<GameConfig>,
 ) {
     for (action_state, mut transform) in query.iter_mut() {
         let mut direction = 0.;
@@ -229,7 +560,98 @@ fn move_paddle(
             direction -= 1.;
         }
 
-        let new_y = transform.translation.y + direction * time.delta_secs() * 300.;
+        let new_y = transform.translation.y + direction * time.delta_secs() * config.paddle_speed;
+        let max_y = game_area.height / 2.0 - 30.0; // Leave some space from borders
+        transform.translation.y = new_y.clamp(-max_y, max_y);
+    }
+}
+
+const AI_REACTION_DEAD_ZONE: f32 = 10.0;
+
+fn ai_move_paddle(
+    ball_query: Query<&Transform, (With<Ball>, Without<Paddle>)>,
+    mut paddle_query: Query<(&PaddleController, &mut Transform), With<Paddle>>,
+    time: Res<Time>,
+    game_area: Res<GameArea>,
+) {
+    let Ok(ball_transform) = ball_query.get_single() else {
+        return;
+    };
+
+    let max_y = game_area.height / 2.0 - 30.0; // Leave some space from borders
+
+    for (controller, mut transform) in &mut paddle_query {
+        let PaddleController::Ai { difficulty } = controller else {
+            continue;
+        };
+
+        transform.translation.y = ai_target_y(
+            transform.translation.y,
+            ball_transform.translation.y,
+            *difficulty,
+            time.delta_secs(),
+            max_y,
+        );
+    }
+}
+
+// Chases the ball once it's outside the dead zone, otherwise holds position
+fn ai_target_y(current_y: f32, ball_y: f32, difficulty: f32, dt: f32, max_y: f32) -> f32 {
+    let offset = ball_y - current_y;
+    if offset.abs() < AI_REACTION_DEAD_ZONE {
+        return current_y;
+    }
+
+    (current_y + offset.signum() * difficulty * dt).clamp(-max_y, max_y)
+}
+
+fn read_local_inputs(
+    mut commands: Commands,
+    local_players: Res<LocalPlayers>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    config: Res<GameConfig>,
+) {
+    let mut local_inputs = HashMap::new();
+
+    for handle in &local_players.0 {
+        let keys = match handle {
+            0 => &config.player_one_keys,
+            _ => &config.player_two_keys,
+        };
+
+        let mut buttons = 0u8;
+        if keyboard.pressed(keys.up) {
+            buttons |= INPUT_UP;
+        }
+        if keyboard.pressed(keys.down) {
+            buttons |= INPUT_DOWN;
+        }
+
+        local_inputs.insert(*handle, PlayerInput { buttons });
+    }
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn move_paddle_p2p(
+    inputs: Res<PlayerInputs<GgrsConfig>>,
+    mut query: Query<(&Player, &mut Transform), With<Paddle>>,
+    time: Res<Time>,
+    game_area: Res<GameArea>,
+    config: Res<GameConfig>,
+) {
+    for (player, mut transform) in &mut query {
+        let (input, _) = inputs[player.0];
+
+        let mut direction = 0.;
+        if input.buttons & INPUT_UP != 0 {
+            direction += 1.;
+        }
+        if input.buttons & INPUT_DOWN != 0 {
+            direction -= 1.;
+        }
+
+        let new_y = transform.translation.y + direction * time.delta_secs() * config.paddle_speed;
         let max_y = game_area.height / 2.0 - 30.0; // Leave some space from borders
         transform.translation.y = new_y.clamp(-max_y, max_y);
     }
@@ -237,28 +659,250 @@ fn move_paddle(
 
 fn check_collisions(
     mut collision_events: EventReader<CollisionStarted>,
-    mut ball_query: Query<&mut LinearVelocity, With<Ball>>,
+    mut ball_query: Query<(&mut LinearVelocity, &Transform), With<Ball>>,
+    paddle_query: Query<(&Transform, &Paddle), Without<Ball>>,
+    border_query: Query<&BorderSide, With<Border>>,
 ) {
     for CollisionStarted(e1, e2) in collision_events.read() {
-        if ball_query.get(*e1).is_ok() && ball_query.get(*e2).is_ok() {
-            for mut ball_velocity in &mut ball_query {
-                ball_velocity.0.x *= -1.;
+        let (ball_entity, other_entity) = if ball_query.get(*e1).is_ok() {
+            (*e1, *e2)
+        } else if ball_query.get(*e2).is_ok() {
+            (*e2, *e1)
+        } else {
+            continue;
+        };
+
+        let Ok((mut ball_velocity, ball_transform)) = ball_query.get_mut(ball_entity) else {
+            continue;
+        };
+
+        if let Ok((paddle_transform, paddle)) = paddle_query.get(other_entity) {
+            ball_velocity.0 = paddle_bounce_velocity(
+                ball_transform.translation.y,
+                paddle_transform.translation.y,
+                paddle.height,
+                ball_velocity.0,
+            );
+        } else if let Ok(border_side) = border_query.get(other_entity) {
+            match border_side {
+                BorderSide::Top | BorderSide::Bottom => ball_velocity.0.y *= -1.,
             }
         }
     }
 }
 
-const ACCELERATION_FACTOR: f32 = 1.001;
+// Reflects the ball off a paddle, steering it by where it hit relative to the paddle's center
+fn paddle_bounce_velocity(
+    ball_y: f32,
+    paddle_y: f32,
+    paddle_height: f32,
+    incoming_velocity: Vec2,
+) -> Vec2 {
+    // Where the ball hit the paddle, normalized to [-1, 1] from its center
+    let offset = ball_y - paddle_y;
+    let factor = (offset / (paddle_height / 2.0)).clamp(-1., 1.);
+
+    let speed = incoming_velocity.length();
+    let direction_toward_center = -incoming_velocity.x.signum();
+    Vec2::new(direction_toward_center, factor).normalize_or_zero() * speed
+}
+
 fn accelerate_ball(
     time: Res<Time>,
     mut timer: ResMut<AccelerationTimer>,
+    config: Res<GameConfig>,
     mut ball_query: Query<&mut LinearVelocity, With<Ball>>,
 ) {
     timer.0.tick(time.delta());
 
     if timer.0.just_finished() {
         for mut velocity in &mut ball_query {
-            velocity.0 *= ACCELERATION_FACTOR;
+            velocity.0 *= config.acceleration_factor;
         }
     }
 }
+
+const BALL_SERVE_SPEED: f32 = 200.0;
+
+fn check_goals(
+    mut score: ResMut<Score>,
+    mut rng: ResMut<ServeRng>,
+    winning_score: Res<WinningScore>,
+    game_area: Res<GameArea>,
+    mut ball_query: Query<(&mut Transform, &mut LinearVelocity), With<Ball>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let half_width = game_area.width / 2.0;
+
+    for (mut transform, mut velocity) in &mut ball_query {
+        if transform.translation.x < -half_width {
+            score.right += 1;
+            reset_ball(&mut transform, &mut velocity, &mut rng);
+        } else if transform.translation.x > half_width {
+            score.left += 1;
+            reset_ball(&mut transform, &mut velocity, &mut rng);
+        }
+    }
+
+    if score.left >= winning_score.0 || score.right >= winning_score.0 {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn reset_ball(transform: &mut Transform, velocity: &mut LinearVelocity, rng: &mut ServeRng) {
+    transform.translation = Vec3::ZERO;
+
+    let angle = rng.0.gen_range(-0.3_f32..0.3_f32);
+    let serve_toward_left = rng.0.gen_bool(0.5);
+    let direction = if serve_toward_left { -1.0 } else { 1.0 };
+
+    velocity.0 = Vec2::new(angle.cos() * direction, angle.sin()) * BALL_SERVE_SPEED;
+}
+
+#[derive(Component)]
+enum ScoreboardSide {
+    Left,
+    Right,
+}
+
+fn spawn_scoreboard(mut commands: Commands) {
+    commands.spawn((
+        Text::new("0"),
+        TextFont {
+            font_size: 48.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.25, 0.25, 0.25)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            left: Val::Percent(40.0),
+            ..default()
+        },
+        ScoreboardSide::Left,
+    ));
+
+    commands.spawn((
+        Text::new("0"),
+        TextFont {
+            font_size: 48.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.25, 0.25, 0.25)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            left: Val::Percent(58.0),
+            ..default()
+        },
+        ScoreboardSide::Right,
+    ));
+}
+
+fn update_scoreboard(score: Res<Score>, mut query: Query<(&mut Text, &ScoreboardSide)>) {
+    if !score.is_changed() {
+        return;
+    }
+
+    for (mut text, side) in &mut query {
+        let value = match side {
+            ScoreboardSide::Left => score.left,
+            ScoreboardSide::Right => score.right,
+        };
+        **text = value.to_string();
+    }
+}
+
+fn spawn_game_over_text(mut commands: Commands, score: Res<Score>) {
+    let winner = if score.left > score.right {
+        "Left"
+    } else {
+        "Right"
+    };
+
+    commands.spawn((
+        Text::new(format!("{winner} player wins!")),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.25, 0.25, 0.25)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(45.0),
+            left: Val::Percent(32.0),
+            ..default()
+        },
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_game_mode_respects_local_versus_flag() {
+        assert_eq!(resolve_game_mode(true), GameMode::LocalVersus);
+        assert_eq!(resolve_game_mode(false), GameMode::SinglePlayer);
+    }
+
+    #[test]
+    fn paddle_bounce_velocity_preserves_speed() {
+        let incoming = Vec2::new(-150.0, 50.0);
+        let bounced = paddle_bounce_velocity(10.0, 0.0, 50.0, incoming);
+        assert!((bounced.length() - incoming.length()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn paddle_bounce_velocity_reverses_toward_center() {
+        // Ball was moving left (toward the paddle); it should bounce back to the right.
+        let bounced = paddle_bounce_velocity(0.0, 0.0, 50.0, Vec2::new(-200.0, 0.0));
+        assert!(bounced.x > 0.0);
+    }
+
+    #[test]
+    fn paddle_bounce_velocity_clamps_extreme_hit_offset() {
+        // A hit far outside the paddle's height should clamp to the maximum (45-degree) steer, not panic.
+        let bounced = paddle_bounce_velocity(1000.0, 0.0, 50.0, Vec2::new(-200.0, 0.0));
+        assert!(bounced.is_finite());
+        assert!((bounced.x - bounced.y).abs() < 1e-3);
+    }
+
+    #[test]
+    fn ai_target_y_holds_position_inside_dead_zone() {
+        let y = ai_target_y(0.0, AI_REACTION_DEAD_ZONE - 1.0, 200.0, 1.0, 300.0);
+        assert_eq!(y, 0.0);
+    }
+
+    #[test]
+    fn ai_target_y_chases_ball_outside_dead_zone() {
+        let y = ai_target_y(0.0, 100.0, 200.0, 1.0, 300.0);
+        assert!(y > 0.0);
+    }
+
+    #[test]
+    fn ai_target_y_clamps_to_play_area() {
+        let y = ai_target_y(295.0, 1000.0, 200.0, 1.0, 300.0);
+        assert_eq!(y, 300.0);
+    }
+
+    #[test]
+    fn game_config_deserialize_fills_missing_fields_with_defaults() {
+        let config: GameConfig = ron::de::from_str("(paddle_speed: 500.0)").unwrap();
+        assert_eq!(config.paddle_speed, 500.0);
+        assert_eq!(config.paddle_height, GameConfig::default().paddle_height);
+    }
+
+    #[test]
+    fn game_config_deserialize_rejects_malformed_input() {
+        let result: Result<GameConfig, _> = ron::de::from_str("not valid ron");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn key_bindings_deserialize_any_keycode() {
+        let bindings: KeyBindings = ron::de::from_str("(up: Space, down: KeyS)").unwrap();
+        assert_eq!(bindings.up, KeyCode::Space);
+        assert_eq!(bindings.down, KeyCode::KeyS);
+    }
+}